@@ -0,0 +1,244 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A built-in auto-player: watches a single game for its `TurnCap`, and when it's our turn,
+//! computes the optimal move by minimax over the 3x3 board and submits it.
+
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use sui_types::base_types::ObjectID;
+
+use crate::{
+    client::Client,
+    game::{Game, GameKind, Winner},
+};
+
+#[derive(Parser, Debug)]
+pub struct AutoplayConfig {
+    /// ID of the game to play.
+    #[clap(long)]
+    pub game_id: ObjectID,
+
+    /// How long to wait between polls of the game's state, in milliseconds.
+    #[clap(long, default_value = "2000")]
+    pub delay_ms: u64,
+
+    /// Make at most one move and then exit, instead of looping until the game ends.
+    #[clap(long)]
+    pub once: bool,
+}
+
+/// A mark on the 3x3 board. `None` represents an empty cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mark {
+    X,
+    O,
+}
+
+impl Mark {
+    fn other(self) -> Mark {
+        match self {
+            Mark::X => Mark::O,
+            Mark::O => Mark::X,
+        }
+    }
+}
+
+/// The 3x3 board, laid out row-major: cell `3 * row + col`.
+type Board = [Option<Mark>; 9];
+
+/// Loop fetching `game_id`'s state and, whenever it's our turn, submit the best available move.
+/// Stops as soon as the game is won, drawn, or (if `config.once` is set) after the first move.
+pub async fn run(client: &mut Client, config: AutoplayConfig) -> Result<()> {
+    loop {
+        let game = client.game(config.game_id).await?;
+        if game.winner != Winner::None {
+            return Ok(());
+        }
+
+        let GameKind::Shared(shared) = &game.kind else {
+            bail!("Autoplay only supports shared games");
+        };
+
+        if client.turn_cap(&game).await.is_err() {
+            tokio::time::sleep(Duration::from_millis(config.delay_ms)).await;
+            continue;
+        }
+
+        let our_mark = mark_to_play(&game)?;
+        let board = board_of(&game)?;
+        let (row, col) = best_move(&board, our_mark).expect("Our turn implies a move is available");
+
+        client
+            .make_shared_move(shared, game.owner, row as u8, col as u8)
+            .await?;
+
+        if config.once {
+            return Ok(());
+        }
+
+        tokio::time::sleep(Duration::from_millis(config.delay_ms)).await;
+    }
+}
+
+/// Work out which mark we should be playing, based on how many marks are already on the board --
+/// a shared tic-tac-toe game alternates `X`, then `O`, so `X` plays after an even number of marks.
+fn mark_to_play(game: &Game) -> Result<Mark> {
+    let GameKind::Shared(shared) = &game.kind else {
+        bail!("Autoplay only supports shared games");
+    };
+
+    Ok(if marks_played(shared) % 2 == 0 {
+        Mark::X
+    } else {
+        Mark::O
+    })
+}
+
+fn marks_played(shared: &crate::game::Shared) -> usize {
+    shared.board.cells.iter().filter(|&&cell| cell != 0).count()
+}
+
+/// Translate the on-chain board (one byte per cell: `0` empty, `1` X, `2` O) into our own
+/// representation.
+fn board_of(game: &Game) -> Result<Board> {
+    let GameKind::Shared(shared) = &game.kind else {
+        bail!("Autoplay only supports shared games");
+    };
+
+    let mut board = [None; 9];
+    for (i, &cell) in shared.board.cells.iter().enumerate() {
+        board[i] = match cell {
+            0 => None,
+            1 => Some(Mark::X),
+            2 => Some(Mark::O),
+            mark => bail!("Unrecognised mark on board: {mark}"),
+        };
+    }
+
+    Ok(board)
+}
+
+/// Find the best move for `player` to make on `board`, returning its (row, col), or `None` if the
+/// board is already full.
+fn best_move(board: &Board, player: Mark) -> Option<(usize, usize)> {
+    empty_cells(board)
+        .map(|cell| {
+            let mut next = *board;
+            next[cell] = Some(player);
+            let score = minimax(&next, player.other(), player, 1);
+            (cell, score)
+        })
+        .max_by_key(|&(_, score)| score)
+        .map(|(cell, _)| (cell / 3, cell % 3))
+}
+
+/// Score `board` from `maximizer`'s perspective, assuming `to_move` plays next, `depth` plies
+/// from the move we're evaluating. A win for `maximizer` scores `10 - depth` (preferring shallower
+/// wins), a loss scores `depth - 10` (preferring deeper losses, i.e. ones that take longer to
+/// arrive), and a draw scores `0`.
+fn minimax(board: &Board, to_move: Mark, maximizer: Mark, depth: i32) -> i32 {
+    if let Some(winner) = winning_mark(board) {
+        return if winner == maximizer {
+            10 - depth
+        } else {
+            depth - 10
+        };
+    }
+
+    let mut cells = empty_cells(board).peekable();
+    if cells.peek().is_none() {
+        return 0;
+    }
+
+    let scores = cells.map(|cell| {
+        let mut next = *board;
+        next[cell] = Some(to_move);
+        minimax(&next, to_move.other(), maximizer, depth + 1)
+    });
+
+    if to_move == maximizer {
+        scores.max().unwrap()
+    } else {
+        scores.min().unwrap()
+    }
+}
+
+fn empty_cells(board: &Board) -> impl Iterator<Item = usize> + '_ {
+    (0..9).filter(|&cell| board[cell].is_none())
+}
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+fn winning_mark(board: &Board) -> Option<Mark> {
+    LINES.into_iter().find_map(|[a, b, c]| {
+        let mark = board[a]?;
+        (board[b] == Some(mark) && board[c] == Some(mark)).then_some(mark)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a [`Board`] from a row-major array of `0` (empty), `1` (X), `2` (O) cells.
+    fn board(cells: [u8; 9]) -> Board {
+        let mut board: Board = [None; 9];
+        for (i, &cell) in cells.iter().enumerate() {
+            board[i] = match cell {
+                0 => None,
+                1 => Some(Mark::X),
+                2 => Some(Mark::O),
+                _ => panic!("Unrecognised mark: {cell}"),
+            };
+        }
+        board
+    }
+
+    #[test]
+    fn takes_an_immediate_win() {
+        // X X . / O O . / . . . -- X completes the top row by playing (0, 2).
+        let b = board([1, 1, 0, 2, 2, 0, 0, 0, 0]);
+        assert_eq!(best_move(&b, Mark::X), Some((0, 2)));
+    }
+
+    #[test]
+    fn blocks_opponents_immediate_win() {
+        // O O . / X . . / . . . -- O threatens the top row, so X must block at (0, 2).
+        let b = board([2, 2, 0, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(best_move(&b, Mark::X), Some((0, 2)));
+    }
+
+    #[test]
+    fn returns_a_move_for_every_empty_cell_count() {
+        let mut b: Board = [None; 9];
+        for n in (1..=9).rev() {
+            assert!(best_move(&b, Mark::X).is_some(), "expected a move with {n} empty cells left");
+            b[n - 1] = Some(if n % 2 == 1 { Mark::X } else { Mark::O });
+        }
+
+        assert_eq!(best_move(&b, Mark::X), None);
+    }
+
+    #[test]
+    fn finds_a_winning_line() {
+        let b = board([1, 1, 1, 2, 2, 0, 0, 0, 0]);
+        assert_eq!(winning_mark(&b), Some(Mark::X));
+    }
+
+    #[test]
+    fn no_winner_on_an_empty_board() {
+        assert_eq!(winning_mark(&[None; 9]), None);
+    }
+}
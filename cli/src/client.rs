@@ -5,7 +5,10 @@ use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
 use clap::Parser;
+use fastcrypto::encoding::{Base64, Encoding};
 use move_core_types::language_storage::StructTag;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
 use shared_crypto::intent::Intent;
 use sui_keys::keystore::AccountKeystore;
 use sui_sdk::{
@@ -13,7 +16,7 @@ use sui_sdk::{
         DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, ObjectChange, SuiData,
         SuiExecutionStatus, SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions,
         SuiObjectResponse, SuiObjectResponseQuery, SuiProtocolConfigValue,
-        SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+        SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
     },
     wallet_context::WalletContext,
     SuiClient,
@@ -33,6 +36,7 @@ use sui_types::{
 
 use crate::{
     game::{self, Game, GameKind, Winner},
+    response::Response,
     turn_cap::TurnCap,
 };
 
@@ -45,11 +49,51 @@ pub struct Connection {
     /// Object ID of the game's package.
     #[clap(long, short, env = "PKG")]
     package_id: ObjectID,
+
+    /// Address to sponsor this player's transactions. If set without `resolve_via`, there is no
+    /// way to obtain the sponsor's co-signature immediately, so one-shot commands refuse to run
+    /// and the build/execute-signed flow (see `build_new_shared_game`/`build_move` and
+    /// `execute_signed`) must be used instead to collect it out-of-band.
+    #[clap(long)]
+    sponsor: Option<SuiAddress>,
+
+    /// Skip local gas estimation and coin selection, and instead submit an unresolved
+    /// transaction (no gas payment, price, or budget) to this URL's transaction-resolution
+    /// endpoint, which fills those fields in server-side and returns a ready-to-sign
+    /// `TransactionData`. Intended for players who hold no gas coins of their own and rely on a
+    /// gas station reachable at this URL.
+    #[clap(long)]
+    resolve_via: Option<Url>,
+
+    /// Print command output as a line of JSON instead of human-oriented text, so that CI and
+    /// bots can consume it deterministically.
+    #[clap(long)]
+    json: bool,
 }
 
-pub(crate) struct Client {
+pub struct Client {
     wallet: WalletContext,
     package: ObjectID,
+    sponsor: Option<SuiAddress>,
+    resolve_via: Option<Url>,
+    json: bool,
+}
+
+/// Body sent to a transaction-resolution endpoint: an unresolved transaction (no gas payment,
+/// price, or budget set), to be filled in server-side.
+#[derive(Serialize)]
+struct ResolveTransactionRequest {
+    sender: SuiAddress,
+    sponsor: Option<SuiAddress>,
+    transaction_bytes: String,
+}
+
+/// Response from a transaction-resolution endpoint: the same transaction, with gas resolved, and
+/// (if a sponsor was requested) that sponsor's signature over it.
+#[derive(Deserialize)]
+struct ResolveTransactionResponse {
+    transaction_bytes: String,
+    sponsor_signature: Option<String>,
 }
 
 impl Client {
@@ -72,9 +116,37 @@ impl Client {
         Ok(Self {
             wallet,
             package: conn.package_id,
+            sponsor: conn.sponsor,
+            resolve_via: conn.resolve_via,
+            json: conn.json,
         })
     }
 
+    /// Whether command output should be printed as JSON (set via the `--json` flag).
+    pub(crate) fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Construct a `Client` directly from an already-configured `wallet`, bypassing the on-disk
+    /// config file lookup in [`Self::new`]. Used by integration tests that get a `WalletContext`
+    /// from a `TestCluster` rather than from a config file on disk.
+    pub fn new_for_test(wallet: WalletContext, package: ObjectID) -> Self {
+        Self {
+            wallet,
+            package,
+            sponsor: None,
+            resolve_via: None,
+            json: false,
+        }
+    }
+
+    /// Switch the wallet's active address to `address`, so that subsequent operations sign (and
+    /// look up owned objects like `TurnCap`s) as that account instead. Used by tests that drive a
+    /// multi-player game from a single keystore holding every player's key.
+    pub fn set_active_address(&mut self, address: SuiAddress) {
+        self.wallet.config.active_address = Some(address);
+    }
+
     /// Fetch the details of a game object from on-chain (can be either shared or owned).
     pub(crate) async fn game(&self, id: ObjectID) -> Result<Game> {
         let client = self.client().await?;
@@ -275,29 +347,42 @@ impl Client {
     }
 
     /// Create a new shared game, between the wallet's active address and the given `opponent`.
-    /// Returns the ID of the Game that was created on success.
-    pub(crate) async fn new_shared_game(&mut self, opponent: SuiAddress) -> Result<ObjectID> {
+    /// Returns a [`Response::GameCreated`] with the ID of the Game that was created on success.
+    pub(crate) async fn new_shared_game(&mut self, opponent: SuiAddress) -> Result<Response> {
         let player = self.wallet.active_address()?;
+        let tx = new_shared_game_ptb(self.package, player, opponent)?;
 
-        let mut builder = ProgrammableTransactionBuilder::new();
-        let x = builder.pure(player)?;
-        let o = builder.pure(opponent)?;
+        let (data, sponsor_sig) = self
+            .build_tx_data_with_sponsor(player, self.sponsor, tx)
+            .await?;
+        self.require_sponsor_sig(&sponsor_sig)?;
+        let id = self.execute_for_game(data, sponsor_sig).await?;
+        Ok(Response::GameCreated { id })
+    }
 
-        builder.programmable_move_call(
-            self.package,
-            Identifier::new("shared").unwrap(),
-            Identifier::new("new").unwrap(),
-            vec![],
-            vec![x, o],
-        );
+    /// Build (but do not sign or submit) the transaction to create a new shared game between the
+    /// wallet's active address and `opponent`. Returns a [`Response::Built`] with the unsigned
+    /// transaction, ready to hand off to an external signer -- see [`Self::execute_signed`].
+    pub(crate) async fn build_new_shared_game(
+        &mut self,
+        opponent: SuiAddress,
+    ) -> Result<Response> {
+        let player = self.wallet.active_address()?;
+        let tx = new_shared_game_ptb(self.package, player, opponent)?;
 
-        let tx = self.build_tx_data(player, builder.finish()).await?;
-        self.execute_for_game(tx).await
+        let (data, sponsor_sig) = self
+            .build_tx_data_with_sponsor(player, self.sponsor, tx)
+            .await?;
+        Response::built(&data, sponsor_sig.as_ref())
     }
 
     /// Delete a shared game, given itself contents and its ownership information (which should be a
-    /// `Owner::Shared`).
-    pub async fn delete_shared_game(&mut self, game: &game::Shared, owner: Owner) -> Result<()> {
+    /// `Owner::Shared`). Returns a [`Response::GameDeleted`] on success.
+    pub async fn delete_shared_game(
+        &mut self,
+        game: &game::Shared,
+        owner: Owner,
+    ) -> Result<Response> {
         let player = self.wallet.active_address()?;
 
         let Owner::Shared {
@@ -323,58 +408,115 @@ impl Client {
             vec![g],
         );
 
-        let data = self.build_tx_data(player, builder.finish()).await?;
-        let tx = self.wallet.sign_transaction(&data);
+        let (data, sponsor_sig) = self
+            .build_tx_data_with_sponsor(player, self.sponsor, builder.finish())
+            .await?;
+        self.require_sponsor_sig(&sponsor_sig)?;
+        let tx = self.sign_transaction(data, sponsor_sig)?;
         self.execute_transaction(tx).await?;
-        Ok(())
+        Ok(Response::GameDeleted)
     }
 
     /// Make a move on a shared game as the wallet's active address. Fails if the active address is
-    /// not meant to make the next move, or if the position is already occupied.
+    /// not meant to make the next move, or if the position is already occupied. Returns a
+    /// [`Response::MoveMade`] populated with the gas and effects of the submitted transaction.
     pub async fn make_shared_move(
         &mut self,
         game: &game::Shared,
         owner: Owner,
         row: u8,
         col: u8,
-    ) -> Result<()> {
+    ) -> Result<Response> {
         let player = self.wallet.active_address()?;
+        let tx = make_move_ptb(self.package, game, owner, row, col)?;
 
-        let Owner::Shared {
-            initial_shared_version,
-        } = owner
-        else {
-            bail!("Game is not shared");
-        };
+        let (data, sponsor_sig) = self
+            .build_tx_data_with_sponsor(player, self.sponsor, tx)
+            .await?;
+        self.require_sponsor_sig(&sponsor_sig)?;
+        let tx = self.sign_transaction(data, sponsor_sig)?;
+        let response = self.execute_transaction(tx).await?;
+        Response::move_made(game.board.id, row, col, &response)
+    }
 
-        let mut builder = ProgrammableTransactionBuilder::new();
+    /// Build (but do not sign or submit) the transaction to place a mark at (`row`, `col`) on
+    /// `game`. Returns a [`Response::Built`] with the unsigned transaction, ready to hand off to
+    /// an external signer, e.g. an air-gapped key holder on a separate machine, who can inspect
+    /// it (see [`Self::inspect_unsigned`]) and countersign it before it is submitted with
+    /// [`Self::execute_signed`]. This, together with [`Self::build_new_shared_game`], lets a
+    /// sponsor and a player each sign offline and hand their signatures back to whoever submits
+    /// the transaction.
+    pub async fn build_move(
+        &mut self,
+        game: &game::Shared,
+        owner: Owner,
+        row: u8,
+        col: u8,
+    ) -> Result<Response> {
+        let player = self.wallet.active_address()?;
+        let tx = make_move_ptb(self.package, game, owner, row, col)?;
 
-        let g = builder.obj(ObjectArg::SharedObject {
-            id: game.board.id,
-            initial_shared_version,
-            mutable: true,
-        })?;
+        let (data, sponsor_sig) = self
+            .build_tx_data_with_sponsor(player, self.sponsor, tx)
+            .await?;
+        Response::built(&data, sponsor_sig.as_ref())
+    }
 
-        let r = builder.pure(row)?;
-        let c = builder.pure(col)?;
+    /// Reconstruct and submit a transaction that was previously built with
+    /// [`Self::build_new_shared_game`] or [`Self::build_move`] and signed out-of-band.
+    /// `transaction_bytes` is the base64 BCS-encoded `TransactionData` that was handed to the
+    /// signer(s), and `signatures` are the base64-encoded signatures collected for it (the
+    /// sender's, and the sponsor's, if one is involved -- including a sponsor signature that was
+    /// already collected while the transaction was built, as reported by [`Response::Built`]).
+    /// Returns a [`Response::TransactionExecuted`].
+    pub async fn execute_signed(
+        &self,
+        transaction_bytes: &str,
+        signatures: &[String],
+    ) -> Result<Response> {
+        let data = decode_tx_data(transaction_bytes)?;
+
+        let sigs = signatures
+            .iter()
+            .map(|sig| {
+                let bytes = Base64::decode(sig)
+                    .map_err(|e| anyhow::anyhow!("Invalid base64 in signature: {e}"))?;
+                GenericSignature::from_bytes(&bytes).context("Failed to deserialize signature")
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        builder.programmable_move_call(
-            self.package,
-            Identifier::new("shared").unwrap(),
-            Identifier::new("place_mark").unwrap(),
-            vec![],
-            vec![g, r, c],
-        );
+        let tx = Transaction::from_generic_sig_data(data, sigs);
+        let response = self.execute_transaction(tx).await?;
+        Response::transaction_executed(&response)
+    }
 
-        let data = self.build_tx_data(player, builder.finish()).await?;
-        let tx = self.wallet.sign_transaction(&data);
-        self.execute_transaction(tx).await?;
-        Ok(())
+    /// Dry-run an unsigned transaction built by [`Self::build_new_shared_game`] or
+    /// [`Self::build_move`], and return its effects, so that whoever is asked to countersign it
+    /// (e.g. an air-gapped key holder, or a sponsor) can verify what they're about to sign before
+    /// doing so.
+    pub async fn inspect_unsigned(
+        &self,
+        transaction_bytes: &str,
+    ) -> Result<SuiTransactionBlockEffects> {
+        let data = decode_tx_data(transaction_bytes)?;
+        let client = self.client().await?;
+
+        let DryRunTransactionBlockResponse { effects, .. } = client
+            .read_api()
+            .dry_run_transaction_block(data)
+            .await
+            .context("Error dry-running transaction")?;
+
+        Ok(effects)
     }
 
     /// Execute a PTB, expecting it to create a shared or owned Game, and return its ObjectID.
-    async fn execute_for_game(&self, data: TransactionData) -> Result<ObjectID> {
-        let tx = self.wallet.sign_transaction(&data);
+    async fn execute_for_game(
+        &self,
+        data: TransactionData,
+        sponsor_sig: Option<GenericSignature>,
+    ) -> Result<ObjectID> {
+        let tx = self.sign_transaction(data, sponsor_sig)?;
         let SuiTransactionBlockResponse {
             object_changes: Some(object_changes),
             ..
@@ -409,25 +551,29 @@ impl Client {
         Ok(game_id)
     }
 
-    /// Like `build_tx_data_with_sponsor`, but without a sponsor.
-    async fn build_tx_data(
-        &self,
-        sender: SuiAddress,
-        tx: ProgrammableTransaction,
-    ) -> Result<TransactionData> {
-        self.build_tx_data_with_sponsor(sender, None, tx).await
-    }
-
-    /// Do gas estimation and coin selection to create a `TransactionData` from a
-    /// `ProgrammableTransaction`. If `sponsor` is provided, it will be used as the gas sponsor, and
-    /// coin selection will fetch coins owned by this address, otherwise coins will be selected from
-    /// the `sender`'s owned objects.
+    /// Build a `TransactionData` from a `ProgrammableTransaction`, ready to be signed. If
+    /// `sponsor` is provided, it will be used as the gas owner. The returned `GenericSignature` is
+    /// the sponsor's co-signature over the resolved transaction, and is only present when gas was
+    /// resolved remotely via [`Self::resolve_via`] with a sponsor set -- callers that get back
+    /// `Some` signature must include it alongside the sender's own when submitting the
+    /// transaction.
+    ///
+    /// If `resolve_via` is configured, gas estimation and coin selection are both delegated to
+    /// that URL's transaction-resolution endpoint, which is useful for players who hold no gas
+    /// coins of their own. Otherwise, gas is estimated locally with a dry-run, and coins are
+    /// selected from `sponsor` (or `sender`, if there is no sponsor).
     async fn build_tx_data_with_sponsor(
         &self,
         sender: SuiAddress,
         sponsor: Option<SuiAddress>,
         tx: ProgrammableTransaction,
-    ) -> Result<TransactionData> {
+    ) -> Result<(TransactionData, Option<GenericSignature>)> {
+        let tx_kind = TransactionKind::ProgrammableTransaction(tx);
+
+        if let Some(url) = &self.resolve_via {
+            return self.resolve_tx_data(url, sender, sponsor, tx_kind).await;
+        }
+
         let client = self.client().await?;
 
         let max_budget = self.max_gas_budget().await?;
@@ -438,8 +584,6 @@ impl Client {
             .await
             .context("Error fetching reference gas price")?;
 
-        let tx_kind = TransactionKind::ProgrammableTransaction(tx);
-
         // Gas Estimation
         let tx_data = client
             .transaction_builder()
@@ -471,13 +615,71 @@ impl Client {
             .await?;
 
         let payment = vec![gas_coin];
-        Ok(if let Some(sponsor) = sponsor {
+        let data = if let Some(sponsor) = sponsor {
             TransactionData::new_with_gas_coins_allow_sponsor(
                 tx_kind, sender, payment, budget, gas_price, sponsor,
             )
         } else {
             TransactionData::new_with_gas_coins(tx_kind, sender, payment, budget, gas_price)
-        })
+        };
+
+        Ok((data, None))
+    }
+
+    /// Submit an unresolved transaction (no gas payment, price, or budget set) to `url`'s
+    /// transaction-resolution endpoint, and return the resolved `TransactionData` it sends back,
+    /// along with the sponsor's signature over it, if the resolver countersigned on `sponsor`'s
+    /// behalf.
+    async fn resolve_tx_data(
+        &self,
+        url: &Url,
+        sender: SuiAddress,
+        sponsor: Option<SuiAddress>,
+        tx_kind: TransactionKind,
+    ) -> Result<(TransactionData, Option<GenericSignature>)> {
+        let unresolved = TransactionData::new_with_gas_coins(
+            tx_kind,
+            sender,
+            /* payment */ vec![],
+            /* budget */ 0,
+            /* price */ 0,
+        );
+
+        let request = ResolveTransactionRequest {
+            sender,
+            sponsor,
+            transaction_bytes: Base64::encode(bcs::to_bytes(&unresolved)?),
+        };
+
+        let response: ResolveTransactionResponse = reqwest::Client::new()
+            .post(url.clone())
+            .json(&request)
+            .send()
+            .await
+            .context("Error contacting transaction-resolution endpoint")?
+            .error_for_status()
+            .context("Transaction-resolution endpoint returned an error")?
+            .json()
+            .await
+            .context("Error parsing transaction-resolution response")?;
+
+        let data: TransactionData = bcs::from_bytes(
+            &Base64::decode(&response.transaction_bytes)
+                .map_err(|e| anyhow::anyhow!("Invalid base64 in resolved transaction: {e}"))?,
+        )
+        .context("Failed to deserialize resolved transaction")?;
+
+        let sponsor_sig = response
+            .sponsor_signature
+            .map(|sig| {
+                let bytes = Base64::decode(&sig)
+                    .map_err(|e| anyhow::anyhow!("Invalid base64 in sponsor signature: {e}"))?;
+                GenericSignature::from_bytes(&bytes)
+                    .context("Failed to deserialize sponsor signature")
+            })
+            .transpose()?;
+
+        Ok((data, sponsor_sig))
     }
 
     /// Find the max budget allowed for a transaction according to the current protocol config.
@@ -518,6 +720,52 @@ impl Client {
             .object_ref())
     }
 
+    /// Check that a transaction about to be signed and submitted immediately has everything it
+    /// needs from its sponsor. One-shot commands (unlike the build/execute-signed split, see
+    /// [`Self::build_new_shared_game`] and [`Self::build_move`]) have no later step at which an
+    /// out-of-band sponsor co-signature could be supplied, so if a sponsor is configured but
+    /// `resolve_via` wasn't able to produce their signature, fail fast with a clear error instead
+    /// of submitting a transaction that the chain is guaranteed to reject for missing a required
+    /// signature.
+    fn require_sponsor_sig(&self, sponsor_sig: &Option<GenericSignature>) -> Result<()> {
+        if self.sponsor.is_some() && sponsor_sig.is_none() {
+            bail!(
+                "A sponsor is set but no sponsor signature was obtained (this requires \
+                 --resolve-via). One-shot commands can't collect a sponsor's co-signature \
+                 out-of-band -- build the transaction instead, have the sponsor sign it \
+                 separately, and submit it with the execute-signed command."
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Sign `data` with the wallet's active address. If `sponsor_sig` is provided, it is combined
+    /// with the sender's signature into the transaction's signature list, rather than relying on
+    /// the wallet alone (which only ever signs on behalf of the sender).
+    fn sign_transaction(
+        &self,
+        data: TransactionData,
+        sponsor_sig: Option<GenericSignature>,
+    ) -> Result<Transaction> {
+        let Some(sponsor_sig) = sponsor_sig else {
+            return Ok(self.wallet.sign_transaction(&data));
+        };
+
+        let sender = self.wallet.active_address()?;
+        let sender_sig = self
+            .wallet
+            .config
+            .keystore
+            .sign_secure(&sender, &data, Intent::sui_transaction())
+            .context("Error signing transaction")?;
+
+        Ok(Transaction::from_generic_sig_data(
+            data,
+            vec![GenericSignature::Signature(sender_sig), sponsor_sig],
+        ))
+    }
+
     /// Execute the transaction, and check whether it succeeded or failed. Transaction execution
     /// failure is treated as an error.
     async fn execute_transaction(&self, tx: Transaction) -> Result<SuiTransactionBlockResponse> {
@@ -545,3 +793,69 @@ impl Client {
             .context("Error fetching client")
     }
 }
+
+/// Build the PTB to create a new shared game between `player` and `opponent` in `package`.
+fn new_shared_game_ptb(
+    package: ObjectID,
+    player: SuiAddress,
+    opponent: SuiAddress,
+) -> Result<ProgrammableTransaction> {
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let x = builder.pure(player)?;
+    let o = builder.pure(opponent)?;
+
+    builder.programmable_move_call(
+        package,
+        Identifier::new("shared").unwrap(),
+        Identifier::new("new").unwrap(),
+        vec![],
+        vec![x, o],
+    );
+
+    Ok(builder.finish())
+}
+
+/// Build the PTB to place a mark at (`row`, `col`) on `game` in `package`. Fails if `owner` is
+/// not `Owner::Shared`.
+fn make_move_ptb(
+    package: ObjectID,
+    game: &game::Shared,
+    owner: Owner,
+    row: u8,
+    col: u8,
+) -> Result<ProgrammableTransaction> {
+    let Owner::Shared {
+        initial_shared_version,
+    } = owner
+    else {
+        bail!("Game is not shared");
+    };
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+
+    let g = builder.obj(ObjectArg::SharedObject {
+        id: game.board.id,
+        initial_shared_version,
+        mutable: true,
+    })?;
+
+    let r = builder.pure(row)?;
+    let c = builder.pure(col)?;
+
+    builder.programmable_move_call(
+        package,
+        Identifier::new("shared").unwrap(),
+        Identifier::new("place_mark").unwrap(),
+        vec![],
+        vec![g, r, c],
+    );
+
+    Ok(builder.finish())
+}
+
+/// Decode a base64 BCS-encoded `TransactionData`, as produced by [`Response::built`].
+fn decode_tx_data(transaction_bytes: &str) -> Result<TransactionData> {
+    let bytes = Base64::decode(transaction_bytes)
+        .map_err(|e| anyhow::anyhow!("Invalid base64 in transaction: {e}"))?;
+    bcs::from_bytes(&bytes).context("Failed to deserialize transaction")
+}
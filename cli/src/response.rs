@@ -0,0 +1,213 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured, machine-readable output for client operations, so that CI and bots can consume
+//! results deterministically instead of parsing human-oriented text (see the `--json` flag on
+//! [`crate::client::Connection`]).
+
+use std::fmt;
+
+use anyhow::{bail, Result};
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::Serialize;
+use sui_sdk::rpc_types::{SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse};
+use sui_types::{
+    base_types::ObjectID, digests::TransactionDigest, signature::GenericSignature,
+    transaction::TransactionData,
+};
+
+use crate::game::{Game, GameKind};
+
+/// The outcome of a single client operation.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
+pub enum Response {
+    /// A new game was created.
+    GameCreated { id: ObjectID },
+
+    /// A move was successfully submitted.
+    MoveMade {
+        game_id: ObjectID,
+        row: u8,
+        col: u8,
+        effects_digest: TransactionDigest,
+        gas_used: i64,
+    },
+
+    /// The current state of a game: nine cells (`0` empty, `1` X, `2` O), the winner (using the
+    /// same encoding as the on-chain `ended` check: `0` no winner yet, `1` draw, `2` a player has
+    /// won), and whose mark plays next (`0` if the game has already ended).
+    GameState { board: [u8; 9], winner: u8, turn: u8 },
+
+    /// A game was deleted.
+    GameDeleted,
+
+    /// A transaction was built but not yet signed or submitted. `transaction_bytes` is the
+    /// base64 BCS-encoded `TransactionData`, ready to hand off to an external signer -- e.g. an
+    /// air-gapped key holder on a separate machine -- who can inspect it and sign it out of band.
+    /// `sponsor_signature` is the sponsor's co-signature over the transaction, already collected
+    /// if gas was resolved remotely via `--resolve-via` with a sponsor set.
+    Built {
+        transaction_bytes: String,
+        sponsor_signature: Option<String>,
+    },
+
+    /// A previously-built transaction, signed out-of-band, was submitted. Unlike `MoveMade`, the
+    /// caller here doesn't know ahead of time what kind of on-chain effects to expect, so only
+    /// the transaction's digest and gas usage are reported.
+    TransactionExecuted {
+        effects_digest: TransactionDigest,
+        gas_used: i64,
+    },
+}
+
+impl Response {
+    /// Build a [`Response::MoveMade`] from the transaction response for a move at (`row`, `col`)
+    /// on `game_id`.
+    pub(crate) fn move_made(
+        game_id: ObjectID,
+        row: u8,
+        col: u8,
+        response: &SuiTransactionBlockResponse,
+    ) -> Result<Self> {
+        let Some(effects) = &response.effects else {
+            bail!("Failed to find effects for transaction");
+        };
+
+        Ok(Response::MoveMade {
+            game_id,
+            row,
+            col,
+            effects_digest: response.digest,
+            gas_used: effects.gas_cost_summary().net_gas_usage(),
+        })
+    }
+
+    /// Build a [`Response::GameState`] from a fetched `game`.
+    pub(crate) fn game_state(game: &Game) -> Result<Self> {
+        let cells = match &game.kind {
+            GameKind::Shared(g) => &g.board.cells,
+            GameKind::Owned(g) => &g.board.cells,
+        };
+
+        let Ok(board) = <[u8; 9]>::try_from(cells.as_slice()) else {
+            bail!("Expected a 3x3 board, got {} cells", cells.len());
+        };
+
+        let winner = match game.winner {
+            crate::game::Winner::None => 0,
+            crate::game::Winner::Draw => 1,
+            crate::game::Winner::Win => 2,
+        };
+
+        let marks_played = board.iter().filter(|&&cell| cell != 0).count();
+        let turn = if winner != 0 {
+            0
+        } else if marks_played % 2 == 0 {
+            1
+        } else {
+            2
+        };
+
+        Ok(Response::GameState {
+            board,
+            winner,
+            turn,
+        })
+    }
+
+    /// Build a [`Response::Built`] from an unsigned `data`, and the sponsor's co-signature over
+    /// it, if one was collected while resolving gas.
+    pub(crate) fn built(
+        data: &TransactionData,
+        sponsor_sig: Option<&GenericSignature>,
+    ) -> Result<Self> {
+        Ok(Response::Built {
+            transaction_bytes: Base64::encode(bcs::to_bytes(data)?),
+            sponsor_signature: sponsor_sig.map(|sig| Base64::encode(sig.as_ref())),
+        })
+    }
+
+    /// Build a [`Response::TransactionExecuted`] from the response of submitting a previously
+    /// built, out-of-band-signed transaction.
+    pub(crate) fn transaction_executed(response: &SuiTransactionBlockResponse) -> Result<Self> {
+        let Some(effects) = &response.effects else {
+            bail!("Failed to find effects for transaction");
+        };
+
+        Ok(Response::TransactionExecuted {
+            effects_digest: response.digest,
+            gas_used: effects.gas_cost_summary().net_gas_usage(),
+        })
+    }
+
+    /// Print this response to stdout, either as a line of JSON (if `json` is set, e.g. because
+    /// the `--json` flag was passed) or as human-oriented text.
+    pub fn print(&self, json: bool) -> Result<()> {
+        if json {
+            println!("{}", serde_json::to_string(self)?);
+        } else {
+            println!("{self}");
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Response::GameCreated { id } => write!(f, "Created game {id}"),
+
+            Response::MoveMade {
+                game_id,
+                row,
+                col,
+                effects_digest,
+                gas_used,
+            } => write!(
+                f,
+                "Played ({row}, {col}) in game {game_id} (tx {effects_digest}, gas used: {gas_used})",
+            ),
+
+            Response::GameState { board, winner, turn } => {
+                for row in board.chunks(3) {
+                    let marks: Vec<_> = row
+                        .iter()
+                        .map(|cell| match cell {
+                            1 => "X",
+                            2 => "O",
+                            _ => ".",
+                        })
+                        .collect();
+                    writeln!(f, "{}", marks.join(" "))?;
+                }
+
+                match winner {
+                    0 => write!(f, "Turn: {}", if *turn == 1 { "X" } else { "O" }),
+                    1 => write!(f, "Draw"),
+                    _ => write!(f, "Winner!"),
+                }
+            }
+
+            Response::GameDeleted => write!(f, "Game deleted"),
+
+            Response::Built {
+                transaction_bytes,
+                sponsor_signature,
+            } => {
+                writeln!(f, "Unsigned transaction (base64 BCS):")?;
+                writeln!(f, "{transaction_bytes}")?;
+                match sponsor_signature {
+                    Some(sig) => write!(f, "Sponsor signature (base64): {sig}"),
+                    None => write!(f, "(no sponsor signature collected)"),
+                }
+            }
+
+            Response::TransactionExecuted {
+                effects_digest,
+                gas_used,
+            } => write!(f, "Executed transaction {effects_digest} (gas used: {gas_used})"),
+        }
+    }
+}
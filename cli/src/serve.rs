@@ -0,0 +1,274 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A long-running daemon that wraps a [`Client`] and exposes its game operations over
+//! JSON-RPC/WebSocket, so that a web frontend or bot process can drive games without shelling out
+//! to the CLI for every move.
+//!
+//! Besides answering requests, the server polls watched games for on-chain changes and pushes a
+//! notification to subscribers as soon as it notices the opponent has moved.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use jsonrpsee::{
+    core::{async_trait, SubscriptionResult},
+    proc_macros::rpc,
+    server::{Server, ServerHandle, SubscriptionMessage, SubscriptionSink},
+    types::ErrorObjectOwned,
+};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use tokio::sync::Mutex;
+
+use crate::{
+    client::{Client, Connection},
+    game::{Game, GameKind, Winner},
+    response::Response,
+};
+
+#[derive(Parser, Debug)]
+pub struct ServeConfig {
+    #[clap(flatten)]
+    pub connection: Connection,
+
+    /// Address to bind the JSON-RPC/WebSocket server to.
+    #[clap(long, default_value = "127.0.0.1:9123")]
+    pub bind: SocketAddr,
+
+    /// How often to poll watched games for on-chain changes.
+    #[clap(long, default_value = "1000")]
+    pub poll_interval_ms: u64,
+}
+
+#[rpc(server, namespace = "ttt")]
+pub trait GameApi {
+    /// Fetch the details of a game object from on-chain.
+    #[method(name = "game")]
+    async fn game(&self, id: ObjectID) -> Result<Game, ErrorObjectOwned>;
+
+    /// Create a new shared game against `opponent`, returning its ID.
+    #[method(name = "newSharedGame")]
+    async fn new_shared_game(&self, opponent: SuiAddress) -> Result<ObjectID, ErrorObjectOwned>;
+
+    /// Place a mark at (`row`, `col`) on the shared game `game_id`.
+    #[method(name = "makeSharedMove")]
+    async fn make_shared_move(
+        &self,
+        game_id: ObjectID,
+        row: u8,
+        col: u8,
+    ) -> Result<(), ErrorObjectOwned>;
+
+    /// Delete the shared game `game_id`.
+    #[method(name = "deleteSharedGame")]
+    async fn delete_shared_game(&self, game_id: ObjectID) -> Result<(), ErrorObjectOwned>;
+
+    /// Look up the `TurnCap` for `game_id` owned by the server wallet's active address, if it's
+    /// that address's turn.
+    #[method(name = "turnCap")]
+    async fn turn_cap(&self, game_id: ObjectID) -> Result<ObjectRef, ErrorObjectOwned>;
+
+    /// Subscribe to updates for `game_id`: the server polls the object on-chain, and pushes the
+    /// latest `Game` every time its version or digest changes.
+    #[subscription(name = "subscribeGame" => "gameUpdate", item = Game)]
+    async fn watch_game(&self, game_id: ObjectID) -> SubscriptionResult;
+}
+
+// Note: the `#[rpc(server, ...)]` macro above already generates a trait named `GameApiServer`
+// (it always appends `Server` to the trait's name), so the type implementing it here has to be
+// called something else to avoid colliding with it.
+pub struct GameApiImpl {
+    client: Arc<Mutex<Client>>,
+    poll_interval: Duration,
+}
+
+impl GameApiImpl {
+    fn new(client: Arc<Mutex<Client>>, poll_interval: Duration) -> Self {
+        Self {
+            client,
+            poll_interval,
+        }
+    }
+
+    fn internal_error(err: anyhow::Error) -> ErrorObjectOwned {
+        ErrorObjectOwned::owned(-32000, err.to_string(), None::<()>)
+    }
+}
+
+#[async_trait]
+impl GameApiServer for GameApiImpl {
+    async fn game(&self, id: ObjectID) -> Result<Game, ErrorObjectOwned> {
+        self.client
+            .lock()
+            .await
+            .game(id)
+            .await
+            .map_err(Self::internal_error)
+    }
+
+    async fn new_shared_game(&self, opponent: SuiAddress) -> Result<ObjectID, ErrorObjectOwned> {
+        let response = self
+            .client
+            .lock()
+            .await
+            .new_shared_game(opponent)
+            .await
+            .map_err(Self::internal_error)?;
+
+        let Response::GameCreated { id } = response else {
+            return Err(Self::internal_error(anyhow::anyhow!(
+                "Unexpected response for a new game"
+            )));
+        };
+
+        Ok(id)
+    }
+
+    async fn make_shared_move(
+        &self,
+        game_id: ObjectID,
+        row: u8,
+        col: u8,
+    ) -> Result<(), ErrorObjectOwned> {
+        let mut client = self.client.lock().await;
+        let game = client.game(game_id).await.map_err(Self::internal_error)?;
+        let GameKind::Shared(shared) = game.kind else {
+            return Err(Self::internal_error(anyhow::anyhow!(
+                "{game_id} is not a shared game"
+            )));
+        };
+
+        client
+            .make_shared_move(&shared, game.owner, row, col)
+            .await
+            .map(|_| ())
+            .map_err(Self::internal_error)
+    }
+
+    async fn delete_shared_game(&self, game_id: ObjectID) -> Result<(), ErrorObjectOwned> {
+        let mut client = self.client.lock().await;
+        let game = client.game(game_id).await.map_err(Self::internal_error)?;
+        let GameKind::Shared(shared) = game.kind else {
+            return Err(Self::internal_error(anyhow::anyhow!(
+                "{game_id} is not a shared game"
+            )));
+        };
+
+        client
+            .delete_shared_game(&shared, game.owner)
+            .await
+            .map(|_| ())
+            .map_err(Self::internal_error)
+    }
+
+    async fn turn_cap(&self, game_id: ObjectID) -> Result<ObjectRef, ErrorObjectOwned> {
+        let mut client = self.client.lock().await;
+        let game = client.game(game_id).await.map_err(Self::internal_error)?;
+        client
+            .turn_cap(&game)
+            .await
+            .map_err(Self::internal_error)
+    }
+
+    async fn watch_game(
+        &self,
+        pending: jsonrpsee::PendingSubscriptionSink,
+        game_id: ObjectID,
+    ) -> SubscriptionResult {
+        let sink = pending.accept().await?;
+        let client = self.client.clone();
+        let poll_interval = self.poll_interval;
+
+        tokio::spawn(async move { poll_and_notify(client, sink, game_id, poll_interval).await });
+
+        Ok(())
+    }
+}
+
+/// Poll `game_id`'s on-chain object on `poll_interval`, and push a notification to `sink`
+/// whenever its version or digest changes from the last time it was observed. Stops as soon as
+/// the subscriber disconnects or the game is fetched successfully and found to be finished.
+async fn poll_and_notify(
+    client: Arc<Mutex<Client>>,
+    sink: SubscriptionSink,
+    game_id: ObjectID,
+    poll_interval: Duration,
+) {
+    let mut seen = None;
+
+    loop {
+        if sink.is_closed() {
+            return;
+        }
+
+        let fetched = client.lock().await.game(game_id).await;
+        let Ok(game) = fetched else {
+            tokio::time::sleep(poll_interval).await;
+            continue;
+        };
+
+        let changed = seen.is_some_and(|last| last != (game.version, game.digest));
+        let finished = game.winner != Winner::None;
+        seen = Some((game.version, game.digest));
+
+        if changed {
+            let Ok(message) = SubscriptionMessage::from_json(&game) else {
+                return;
+            };
+
+            if sink.send(message).await.is_err() {
+                return;
+            }
+        }
+
+        if finished {
+            return;
+        }
+
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// Bind the JSON-RPC/WebSocket server described by `config` and start it, returning the address
+/// it actually bound to (useful when `config.bind` asks for an ephemeral port, e.g. in tests) and
+/// a handle that can be used to shut it down.
+pub async fn bind(config: ServeConfig) -> Result<(SocketAddr, ServerHandle)> {
+    let client = Client::new(config.connection)?;
+    let poll_interval = Duration::from_millis(config.poll_interval_ms);
+    let (addr, handle, _client) = bind_with_client(client, config.bind, poll_interval).await?;
+    Ok((addr, handle))
+}
+
+/// As [`bind`], but for callers (e.g. tests) that already have a [`Client`] and don't want to go
+/// through [`Client::new`]'s on-disk config file lookup to build one. Also returns the shared,
+/// lockable handle to `client` that the server is using, so that a test can keep mutating it
+/// (e.g. switching its active address between moves) after the server has taken it over.
+pub async fn bind_with_client(
+    client: Client,
+    bind: SocketAddr,
+    poll_interval: Duration,
+) -> Result<(SocketAddr, ServerHandle, Arc<Mutex<Client>>)> {
+    let client = Arc::new(Mutex::new(client));
+
+    let server = Server::builder()
+        .build(bind)
+        .await
+        .context("Error binding JSON-RPC server")?;
+
+    let addr = server
+        .local_addr()
+        .context("Error reading bound address")?;
+
+    let rpc = GameApiImpl::new(client.clone(), poll_interval);
+    let handle: ServerHandle = server.start(rpc.into_rpc());
+
+    Ok((addr, handle, client))
+}
+
+/// Start the JSON-RPC/WebSocket server described by `config`, and run it until it is shut down.
+pub async fn run(config: ServeConfig) -> Result<()> {
+    let (_, handle) = bind(config).await?;
+    handle.stopped().await;
+    Ok(())
+}
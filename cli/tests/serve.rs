@@ -0,0 +1,163 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Integration tests for the `serve` subsystem: spin up the JSON-RPC server against a local
+//! network, and exercise a full two-player game over the RPC interface rather than the CLI.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use jsonrpsee::{
+    core::client::{ClientT, SubscriptionClientT},
+    rpc_params,
+    server::ServerHandle,
+    ws_client::{WsClient, WsClientBuilder},
+};
+use sui_sdk::wallet_context::WalletContext;
+use sui_types::base_types::ObjectID;
+use test_cluster::TestClusterBuilder;
+use tokio::sync::Mutex;
+
+use tic_tac_toe_cli::{
+    client::Client,
+    game::{Game, Winner},
+    serve,
+};
+
+/// Path to the tic-tac-toe Move package that the games played in these tests are published
+/// against, relative to this crate.
+const PACKAGE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../move");
+
+/// Publish the tic-tac-toe Move package to `wallet`'s network, and return its package ID.
+async fn publish_tic_tac_toe_package(wallet: &WalletContext) -> ObjectID {
+    let package_ref =
+        sui_test_transaction_builder::publish_package(wallet, PathBuf::from(PACKAGE_PATH)).await;
+    package_ref.0
+}
+
+/// Start a `serve` server wrapping `client`, bound to an ephemeral port, and return a connected
+/// WebSocket client for it, a handle to shut it down, and a shared handle to `client` itself
+/// (e.g. so the test can switch its active address between moves).
+async fn spawn_server(client: Client) -> (WsClient, ServerHandle, Arc<Mutex<Client>>) {
+    let (addr, handle, client) = serve::bind_with_client(
+        client,
+        "127.0.0.1:0".parse().unwrap(),
+        Duration::from_millis(200),
+    )
+    .await
+    .expect("failed to bind server");
+
+    let ws = WsClientBuilder::default()
+        .build(format!("ws://{addr}"))
+        .await
+        .expect("failed to connect to server");
+
+    (ws, handle, client)
+}
+
+#[tokio::test]
+async fn plays_a_full_game_over_rpc() {
+    let cluster = TestClusterBuilder::new().build().await;
+    let package_id = publish_tic_tac_toe_package(&cluster.wallet).await;
+
+    let x = cluster.get_address(0);
+    let o = cluster.get_address(1);
+
+    let mut wallet = cluster.wallet;
+    wallet.config.active_address = Some(x);
+    let client = Client::new_for_test(wallet, package_id);
+
+    let (ws, _server, client) = spawn_server(client).await;
+
+    let game_id: ObjectID = ws
+        .request("ttt_newSharedGame", rpc_params![o])
+        .await
+        .expect("failed to create game");
+
+    // X completes the top row -- (0, 0), (0, 1), (0, 2) -- while O plays the second row in
+    // between, which neither blocks nor completes anything.
+    let moves = [
+        (x, 0u8, 0u8),
+        (o, 1u8, 0u8),
+        (x, 0u8, 1u8),
+        (o, 1u8, 1u8),
+        (x, 0u8, 2u8),
+    ];
+
+    for (player, row, col) in moves {
+        client.lock().await.set_active_address(player);
+        ws.request::<(), _>("ttt_makeSharedMove", rpc_params![game_id, row, col])
+            .await
+            .expect("move failed");
+    }
+
+    let game: Game = ws
+        .request("ttt_game", rpc_params![game_id])
+        .await
+        .expect("failed to fetch game");
+
+    assert_eq!(game.winner, Winner::Win);
+}
+
+#[tokio::test]
+async fn subscription_pushes_updates_and_ends_when_game_finishes() {
+    let cluster = TestClusterBuilder::new().build().await;
+    let package_id = publish_tic_tac_toe_package(&cluster.wallet).await;
+
+    let x = cluster.get_address(0);
+    let o = cluster.get_address(1);
+
+    let mut wallet = cluster.wallet;
+    wallet.config.active_address = Some(x);
+    let client = Client::new_for_test(wallet, package_id);
+
+    let (ws, _server, client) = spawn_server(client).await;
+
+    let game_id: ObjectID = ws
+        .request("ttt_newSharedGame", rpc_params![o])
+        .await
+        .expect("failed to create game");
+
+    let mut subscription: jsonrpsee::core::client::Subscription<Game> = ws
+        .subscribe(
+            "ttt_subscribeGame",
+            rpc_params![game_id],
+            "ttt_unsubscribeGame",
+        )
+        .await
+        .expect("failed to subscribe to game");
+
+    // X completes the top row -- (0, 0), (0, 1), (0, 2) -- while O plays the second row in
+    // between, which neither blocks nor completes anything.
+    let moves = [
+        (x, 0u8, 0u8),
+        (o, 1u8, 0u8),
+        (x, 0u8, 1u8),
+        (o, 1u8, 1u8),
+        (x, 0u8, 2u8),
+    ];
+
+    let mut last_seen = None;
+    let mut last_update = None;
+    for (player, row, col) in moves {
+        client.lock().await.set_active_address(player);
+        ws.request::<(), _>("ttt_makeSharedMove", rpc_params![game_id, row, col])
+            .await
+            .expect("move failed");
+
+        let update = subscription
+            .next()
+            .await
+            .expect("subscription ended before the game finished")
+            .expect("subscription yielded an error");
+
+        let seen = (update.version, update.digest);
+        assert_ne!(Some(seen), last_seen, "expected a fresh update for move ({row}, {col})");
+        last_seen = Some(seen);
+        last_update = Some(update);
+    }
+
+    // The last move completes the top row, so the final push should already show X as the
+    // winner, and the server should stop polling (ending the subscription) right after.
+    assert_eq!(last_update.unwrap().winner, Winner::Win);
+    assert!(subscription.next().await.is_none());
+}